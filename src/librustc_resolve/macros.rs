@@ -72,6 +72,8 @@ pub struct LegacyBinding<'a> {
     pub name: ast::Name,
     ext: Rc<SyntaxExtension>,
     pub span: Span,
+    // Set once some invocation actually resolves to this macro.
+    pub used: Cell<bool>,
 }
 
 pub enum MacroBinding<'a> {
@@ -149,10 +151,16 @@ impl<'a> base::Resolver for Resolver<'a> {
             name: def.ident.name,
             ext: Rc::new(macro_rules::compile(&self.session.parse_sess, &def)),
             span: def.span,
+            used: Cell::new(false),
         });
         invocation.legacy_scope.set(LegacyScope::Binding(binding));
         self.macro_names.insert(def.ident.name);
 
+        // Exported macros may be used by other crates, so only track non-exported ones.
+        if !export {
+            self.local_macro_rules.push(binding);
+        }
+
         if export {
             def.id = self.next_node_id();
             DefCollector::new(&mut self.definitions).with_parent(CRATE_DEF_INDEX, |collector| {
@@ -244,7 +252,10 @@ impl<'a> base::Resolver for Resolver<'a> {
 
         let name = path[0].name;
         let result = match self.resolve_legacy_scope(&invocation.legacy_scope, name, false) {
-            Some(MacroBinding::Legacy(binding)) => Ok(binding.ext.clone()),
+            Some(MacroBinding::Legacy(binding)) => {
+                binding.used.set(true);
+                Ok(binding.ext.clone())
+            }
             Some(MacroBinding::Modern(binding)) => Ok(binding.get_macro(self)),
             None => match self.resolve_lexical_macro_path_segment(name, MacroNS, None) {
                 Ok(binding) => Ok(binding.get_macro(self)),
@@ -267,6 +278,75 @@ impl<'a> base::Resolver for Resolver<'a> {
 }
 
 impl<'a> Resolver<'a> {
+    // Like `resolve_macro`, but emits no diagnostics and records no deferred state.
+    // Still takes `&mut self` and sets `current_module` as a lookup side effect, so
+    // interleaving this with real resolution work is not safe.
+    pub fn try_resolve_macro(&mut self, scope: Mark, path: &ast::Path)
+                             -> Option<Rc<SyntaxExtension>> {
+        let ast::Path { ref segments, global, .. } = *path;
+        if segments.iter().any(|segment| !segment.parameters.is_empty()) {
+            return None;
+        }
+
+        let path_scope = if global { PathScope::Global } else { PathScope::Lexical };
+        let path: Vec<_> = segments.iter().map(|seg| seg.identifier).collect();
+        let invocation = self.invocations[&scope];
+        self.current_module = invocation.module.get();
+
+        if path.len() > 1 || global {
+            if !self.use_extern_macros {
+                return None;
+            }
+
+            return match self.resolve_path(&path, path_scope, Some(MacroNS), None) {
+                PathResult::NonModule(path_res) => Some(self.get_macro(path_res.base_def)),
+                _ => None,
+            };
+        }
+
+        let name = path[0].name;
+        match self.probe_legacy_scope(&invocation.legacy_scope, name) {
+            Some(MacroBinding::Legacy(binding)) => Some(binding.ext.clone()),
+            Some(MacroBinding::Modern(binding)) => Some(binding.get_macro(self)),
+            None => self.resolve_lexical_macro_path_segment(name, MacroNS, None)
+                .ok()
+                .map(|binding| binding.get_macro(self)),
+        }
+    }
+
+    // Like `resolve_legacy_scope`, but never pushes onto `disallowed_shadowing` or
+    // `lexical_macro_resolutions` — unlike `resolve_legacy_scope(..., false)`, this is
+    // safe to call speculatively without affecting later diagnostics.
+    fn probe_legacy_scope(&self, mut scope: &'a Cell<LegacyScope<'a>>, name: Name)
+                          -> Option<MacroBinding<'a>> {
+        let mut binding = None;
+        loop {
+            match scope.get() {
+                LegacyScope::Empty => break,
+                LegacyScope::Expansion(invocation) => {
+                    match invocation.expansion.get() {
+                        LegacyScope::Invocation(_) => scope.set(invocation.legacy_scope.get()),
+                        LegacyScope::Empty => scope = &invocation.legacy_scope,
+                        _ => scope = &invocation.expansion,
+                    }
+                }
+                LegacyScope::Invocation(invocation) => scope = &invocation.legacy_scope,
+                LegacyScope::Binding(potential_binding) => {
+                    if potential_binding.name == name {
+                        binding = Some(potential_binding);
+                        break
+                    }
+                    scope = &potential_binding.parent;
+                }
+            }
+        }
+
+        match binding {
+            Some(binding) => Some(MacroBinding::Legacy(binding)),
+            None => self.builtin_macros.get(&name).cloned().map(MacroBinding::Modern),
+        }
+    }
+
     // Resolve the initial segment of a non-global macro path (e.g. `foo` in `foo::bar!();`)
     pub fn resolve_lexical_macro_path_segment(&mut self,
                                               name: Name,
@@ -346,6 +426,9 @@ impl<'a> Resolver<'a> {
                         if (!self.use_extern_macros || record_used) && relative_depth > 0 {
                             self.disallowed_shadowing.push(potential_binding);
                         }
+                        if record_used {
+                            potential_binding.used.set(true);
+                        }
                         binding = Some(potential_binding);
                         break
                     }
@@ -406,7 +489,30 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    // Non-exported `macro_rules!` definitions that were never resolved to, for the dead-code
+    // lint. Covers local legacy macros only — `NameBinding` (imported/builtin macros) has no
+    // usedness tracking here, so unused `#[macro_use]` imports aren't reported by this query.
+    pub fn dead_macro_rules(&self) -> Vec<(Name, Span)> {
+        self.local_macro_rules.iter()
+            .filter(|binding| !binding.used.get())
+            .map(|binding| (binding.name, binding.span))
+            .collect()
+    }
+
     fn suggest_macro_name(&mut self, name: &str, err: &mut DiagnosticBuilder<'a>) {
+        // Prefer names bound in the current module's macro namespace (including macros
+        // brought in via `use`); `macro_rules!` names are only searched afterwards.
+        let in_scope: Vec<Name> = self.current_module.resolutions.borrow().iter()
+            .filter(|&(&(_, ns), resolution)| ns == MacroNS && resolution.borrow().binding().is_some())
+            .map(|(&(name, _), _)| name)
+            .collect();
+        if let Some(suggestion) = find_best_match_for_name(in_scope.iter(), name, None) {
+            if suggestion != name {
+                err.help(&format!("did you mean `{}!`?", suggestion));
+                return;
+            }
+        }
+
         if let Some(suggestion) = find_best_match_for_name(self.macro_names.iter(), name, None) {
             if suggestion != name {
                 err.help(&format!("did you mean `{}!`?", suggestion));